@@ -21,6 +21,7 @@ use super::{
     TypeGenerator,
     TypeParameter,
     TypePath,
+    Variant,
 };
 use heck::CamelCase as _;
 use proc_macro2::TokenStream;
@@ -33,6 +34,11 @@ use scale_info::{
     TypeDef,
     TypeDefPrimitive,
 };
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+use std::iter::FromIterator;
 
 /// Representation of a type which consists of a set of fields. Used to generate Rust code for
 /// either a standalone `struct` definition, or an `enum` variant.
@@ -46,15 +52,23 @@ pub struct CompositeDef {
     pub kind: CompositeDefKind,
     /// The fields of the type, which are either all named or all unnamed.
     pub fields: CompositeDefFields,
+    /// Documentation lifted from the metadata, emitted as `#[doc = "..."]` attributes.
+    ///
+    /// Empty unless the `generate_docs` flag passed to the constructor is set.
+    pub docs: Vec<String>,
 }
 
 impl CompositeDef {
-    /// Construct a definition which will generate code for a standalone `struct`.
+    /// Construct a definition which will generate code for a standalone `struct`. Prefer
+    /// [`struct_def_or_substitute`] at the top-level codegen call site, so configured type
+    /// substitutions are respected.
     pub fn struct_def(
         ident: &str,
         type_params: TypeDefParameters,
         fields_def: CompositeDefFields,
         field_visibility: Option<syn::Visibility>,
+        docs: &[String],
+        generate_docs: bool,
         type_gen: &TypeGenerator,
     ) -> Self {
         let mut derives = type_gen.derives().clone();
@@ -95,23 +109,102 @@ impl CompositeDef {
                 field_visibility,
             },
             fields: fields_def,
+            docs: if generate_docs { docs.to_vec() } else { Default::default() },
         }
     }
 
-    /// Construct a definition which will generate code for an `enum` variant.
-    pub fn enum_variant_def(ident: &str, fields: CompositeDefFields) -> Self {
+    /// Construct a definition which will generate code for an `enum` variant. `index` is the
+    /// variant's index as declared in the metadata, preserved via `#[codec(index = ..)]` so
+    /// `Encode`/`Decode` stay correct even if declaration order doesn't match it. Prefer
+    /// [`enum_variant_defs`] at the top-level codegen call site, so each variant's index, docs
+    /// and fields are threaded through correctly.
+    pub fn enum_variant_def(
+        ident: &str,
+        index: u8,
+        fields: CompositeDefFields,
+        docs: &[String],
+        generate_docs: bool,
+    ) -> Self {
         let name = format_ident!("{}", ident);
         Self {
             name,
-            kind: CompositeDefKind::EnumVariant,
+            kind: CompositeDefKind::EnumVariant { index },
             fields,
+            docs: if generate_docs { docs.to_vec() } else { Default::default() },
         }
     }
 }
 
+/// Builds the [`CompositeDef`]s for every variant of a metadata `enum`. The top-level codegen
+/// loop assembling an enum's variants should call this instead of `CompositeDef::enum_variant_def`
+/// directly, so each variant's metadata index, docs and fields are threaded through correctly.
+pub fn enum_variant_defs(
+    enum_type_id: u32,
+    variants: &[Variant],
+    parent_type_params: &[TypeParameter],
+    generate_docs: bool,
+    boxed_fields: &BoxedFields,
+    type_substitutes: &TypeSubstitutes,
+    type_gen: &TypeGenerator,
+) -> Vec<CompositeDef> {
+    variants
+        .iter()
+        .map(|variant| {
+            let fields = CompositeDefFields::from_scale_info_fields(
+                variant.name(),
+                variant.fields(),
+                enum_type_id,
+                parent_type_params,
+                generate_docs,
+                boxed_fields,
+                type_substitutes,
+                type_gen,
+            );
+            CompositeDef::enum_variant_def(
+                variant.name(),
+                variant.index(),
+                fields,
+                variant.docs(),
+                generate_docs,
+            )
+        })
+        .collect()
+}
+
+/// Builds a [`CompositeDef::struct_def`] for a metadata composite type at `path`, or returns
+/// `None` if a type substitute is configured for it. The top-level codegen loop should call
+/// this instead of `CompositeDef::struct_def` directly, so configured substitutions are
+/// respected.
+#[allow(clippy::too_many_arguments)]
+pub fn struct_def_or_substitute(
+    ident: &str,
+    path: &[String],
+    type_params: TypeDefParameters,
+    fields_def: CompositeDefFields,
+    field_visibility: Option<syn::Visibility>,
+    docs: &[String],
+    generate_docs: bool,
+    type_substitutes: &TypeSubstitutes,
+    type_gen: &TypeGenerator,
+) -> Option<CompositeDef> {
+    if !should_generate_composite(type_substitutes, path) {
+        return None
+    }
+    Some(CompositeDef::struct_def(
+        ident,
+        type_params,
+        fields_def,
+        field_visibility,
+        docs,
+        generate_docs,
+        type_gen,
+    ))
+}
+
 impl quote::ToTokens for CompositeDef {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = &self.name;
+        let docs = doc_attrs(&self.docs);
 
         let decl = match &self.kind {
             CompositeDefKind::Struct {
@@ -130,14 +223,17 @@ impl quote::ToTokens for CompositeDef {
                 .then(|| quote!(;));
 
                 quote! {
+                    #docs
                     #derives
                     pub struct #name #type_params #fields #trailing_semicolon
                 }
             }
-            CompositeDefKind::EnumVariant => {
+            CompositeDefKind::EnumVariant { index } => {
                 let fields = self.fields.to_enum_variant_field_tokens();
 
                 quote! {
+                    #docs
+                    #[codec(index = #index)]
                     #name #fields
                 }
             }
@@ -146,6 +242,26 @@ impl quote::ToTokens for CompositeDef {
     }
 }
 
+/// Turns a set of doc lines lifted from the metadata into `#[doc = "..."]` attributes.
+///
+/// Returns an empty token stream if `docs` is empty, so callers can splice the result in
+/// unconditionally.
+fn doc_attrs(docs: &[String]) -> TokenStream {
+    quote! { #( #[doc = #docs] )* }
+}
+
+/// Whether a field must be wrapped in `Box`: never for a [`CompositeDefFieldPath::Substitute`]
+/// (its layout is opaque to subxt), otherwise whatever `boxed_fields` decided for this edge.
+fn field_should_box(
+    type_path: &CompositeDefFieldPath,
+    parent_type_id: u32,
+    field_type_id: u32,
+    boxed_fields: &BoxedFields,
+) -> bool {
+    matches!(type_path, CompositeDefFieldPath::Generated(_))
+        && boxed_fields.should_box(parent_type_id, field_type_id)
+}
+
 /// Which kind of composite type are we generating, either a standalone `struct` or an `enum`
 /// variant.
 #[derive(Debug)]
@@ -157,7 +273,11 @@ pub enum CompositeDefKind {
         field_visibility: Option<syn::Visibility>,
     },
     /// Comprises a variant of a Rust `enum`.
-    EnumVariant,
+    EnumVariant {
+        /// The variant's index as declared in the metadata, emitted as `#[codec(index = ..)]`
+        /// so that positional drift in the generated enum can never corrupt encoding.
+        index: u8,
+    },
 }
 
 /// Encapsulates the composite fields, keeping the invariant that all fields are either named or
@@ -171,10 +291,19 @@ pub enum CompositeDefFields {
 
 impl CompositeDefFields {
     /// Construct a new set of composite fields from the supplied [`::scale_info::Field`]s.
+    ///
+    /// `parent_type_id` is the metadata id of the composite type (or enum variant's parent
+    /// enum) these fields belong to, and is needed to decide which fields must be boxed to
+    /// break a cycle in the type graph.
+    #[allow(clippy::too_many_arguments)]
     pub fn from_scale_info_fields(
         name: &str,
         fields: &[Field],
+        parent_type_id: u32,
         parent_type_params: &[TypeParameter],
+        generate_docs: bool,
+        boxed_fields: &BoxedFields,
+        type_substitutes: &TypeSubstitutes,
         type_gen: &TypeGenerator,
     ) -> Self {
         if fields.is_empty() {
@@ -185,12 +314,34 @@ impl CompositeDefFields {
         let mut unnamed_fields = Vec::new();
 
         for field in fields {
-            let type_path =
-                type_gen.resolve_type_path(field.ty().id(), parent_type_params);
+            let field_type_id = field.ty().id();
+            let resolved_type = type_gen.resolve_type(field_type_id);
+            let resolved_path = resolved_type.path();
+
+            let type_path = match type_substitutes.get(resolved_path.segments()) {
+                Some(substitute_path) => {
+                    CompositeDefFieldPath::Substitute(substitute_path.clone())
+                }
+                None => CompositeDefFieldPath::Generated(
+                    type_gen.resolve_type_path(field_type_id, parent_type_params),
+                ),
+            };
+
+            let docs = if generate_docs {
+                field.docs().to_vec()
+            } else {
+                Default::default()
+            };
+
+            let should_box =
+                field_should_box(&type_path, parent_type_id, field_type_id, boxed_fields);
+
             let field_type = CompositeDefFieldType::new(
-                field.ty().id(),
+                field_type_id,
                 type_path,
                 field.type_name().cloned(),
+                docs,
+                should_box,
             );
 
             if let Some(name) = field.name() {
@@ -240,8 +391,9 @@ impl CompositeDefFields {
             }
             Self::Named(ref fields) => {
                 let fields = fields.iter().map(|(name, ty)| {
+                    let docs = doc_attrs(&ty.docs);
                     let compact_attr = ty.compact_attr();
-                    quote! { #compact_attr #visibility #name: #ty }
+                    quote! { #docs #compact_attr #visibility #name: #ty }
                 });
                 let marker = phantom_data.map(|phantom_data| {
                     quote!(
@@ -258,8 +410,9 @@ impl CompositeDefFields {
             }
             Self::Unnamed(ref fields) => {
                 let fields = fields.iter().map(|ty| {
+                    let docs = doc_attrs(&ty.docs);
                     let compact_attr = ty.compact_attr();
-                    quote! { #compact_attr #visibility #ty }
+                    quote! { #docs #compact_attr #visibility #ty }
                 });
                 let marker = phantom_data.map(|phantom_data| {
                     quote!(
@@ -283,15 +436,17 @@ impl CompositeDefFields {
             Self::NoFields => quote! {},
             Self::Named(ref fields) => {
                 let fields = fields.iter().map(|(name, ty)| {
+                    let docs = doc_attrs(&ty.docs);
                     let compact_attr = ty.compact_attr();
-                    quote! { #compact_attr #name: #ty }
+                    quote! { #docs #compact_attr #name: #ty }
                 });
                 quote!( { #( #fields, )* } )
             }
             Self::Unnamed(ref fields) => {
                 let fields = fields.iter().map(|ty| {
+                    let docs = doc_attrs(&ty.docs);
                     let compact_attr = ty.compact_attr();
-                    quote! { #compact_attr #ty }
+                    quote! { #docs #compact_attr #ty }
                 });
                 quote! { ( #( #fields, )* ) }
             }
@@ -299,37 +454,205 @@ impl CompositeDefFields {
     }
 }
 
+/// A directed graph over metadata type ids, used to decide which composite fields must be
+/// boxed to give every generated type a finite size.
+///
+/// An edge `from -> to` means the composite type `from` embeds a field of type `to` *directly*:
+/// edges that would otherwise pass through `Box`, `Vec`, `BTreeMap`, `Option`, etc. must never
+/// be added, since those already break the size recursion on their own.
+#[derive(Debug, Default)]
+pub struct TypeSizeGraph {
+    edges: BTreeMap<u32, Vec<u32>>,
+}
+
+impl TypeSizeGraph {
+    /// Construct an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a direct embedding edge: the composite type `from` has a field of type `to` with
+    /// no intervening `Box`/`Vec`/`BTreeMap`/`Option`/etc indirection.
+    pub fn add_edge(&mut self, from: u32, to: u32) {
+        self.edges.entry(from).or_default().push(to);
+    }
+
+    /// Runs a DFS over the graph and returns the set of edges that must be boxed so that every
+    /// type in the graph has a finite size.
+    ///
+    /// For every back-edge encountered (an edge into a node already on the current DFS stack),
+    /// exactly that edge is marked for boxing, closing the cycle it belongs to. Visiting nodes
+    /// in ascending id order, and each node's edges in insertion order, makes the choice
+    /// reproducible across runs over the same metadata.
+    fn fields_to_box(&self) -> BTreeSet<(u32, u32)> {
+        let mut boxed = BTreeSet::new();
+        let mut visited = BTreeSet::new();
+        let mut on_stack = BTreeSet::new();
+
+        for &node in self.edges.keys() {
+            if !visited.contains(&node) {
+                self.visit(node, &mut visited, &mut on_stack, &mut boxed);
+            }
+        }
+
+        boxed
+    }
+
+    fn visit(
+        &self,
+        node: u32,
+        visited: &mut BTreeSet<u32>,
+        on_stack: &mut BTreeSet<u32>,
+        boxed: &mut BTreeSet<(u32, u32)>,
+    ) {
+        visited.insert(node);
+        on_stack.insert(node);
+
+        if let Some(targets) = self.edges.get(&node) {
+            for &target in targets {
+                if on_stack.contains(&target) {
+                    // Back-edge: `node -> target` closes a cycle. Box exactly this edge so the
+                    // cycle can never corrupt the generated type's size.
+                    boxed.insert((node, target));
+                } else if !visited.contains(&target) {
+                    self.visit(target, visited, on_stack, boxed);
+                }
+            }
+        }
+
+        on_stack.remove(&node);
+    }
+
+    /// Runs the cycle-detection DFS exactly once and returns a cheap lookup of which edges must
+    /// be boxed, instead of recomputing it on every query.
+    pub fn into_boxed_fields(self) -> BoxedFields {
+        BoxedFields(self.fields_to_box())
+    }
+}
+
+/// The set of `(from, to)` edges that [`TypeSizeGraph::into_boxed_fields`] decided must be
+/// boxed, computed once up front rather than on every [`BoxedFields::should_box`] call.
+#[derive(Debug, Default)]
+pub struct BoxedFields(BTreeSet<(u32, u32)>);
+
+impl BoxedFields {
+    /// Returns `true` if the direct edge `from -> to` must be boxed.
+    pub fn should_box(&self, from: u32, to: u32) -> bool {
+        self.0.contains(&(from, to))
+    }
+}
+
+/// A configured set of metadata type-path substitutions, mapping a fully-qualified metadata
+/// type path (e.g. `["sp_core", "crypto", "AccountId32"]`) to a user-supplied Rust path to use
+/// in its place. The matched composite type is skipped entirely when generating type
+/// definitions (see [`should_generate_composite`]). Built once from user config (see the
+/// [`FromIterator`] impl below) and held by the generator alongside its other settings, the same
+/// way `generate_docs` is.
+#[derive(Debug, Default)]
+pub struct TypeSubstitutes {
+    substitutes: BTreeMap<Vec<String>, syn::Path>,
+}
+
+impl TypeSubstitutes {
+    /// Construct an empty set of substitutions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configure `path` to be replaced with `substitute` wherever it's referenced, instead of
+    /// being generated.
+    pub fn insert(&mut self, path: Vec<String>, substitute: syn::Path) -> Option<syn::Path> {
+        self.substitutes.insert(path, substitute)
+    }
+
+    /// Returns the substitute path configured for a metadata type whose path segments are
+    /// `path`, if any.
+    pub fn get(&self, path: &[String]) -> Option<&syn::Path> {
+        self.substitutes.get(path)
+    }
+}
+
+/// Builds a [`TypeSubstitutes`] from a user-supplied collection of `(metadata path, substitute
+/// path)` pairs, e.g. parsed out of the generator's config: `user_config.collect()`.
+impl FromIterator<(Vec<String>, syn::Path)> for TypeSubstitutes {
+    fn from_iter<I: IntoIterator<Item = (Vec<String>, syn::Path)>>(iter: I) -> Self {
+        Self {
+            substitutes: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Returns `false` if a substitute is configured for the metadata type at `path`, meaning its
+/// composite definition must be skipped entirely — only field references to it are rendered,
+/// via [`CompositeDefFieldPath::Substitute`].
+pub fn should_generate_composite(type_substitutes: &TypeSubstitutes, path: &[String]) -> bool {
+    type_substitutes.get(path).is_none()
+}
+
+/// The Rust path a field will be rendered with: either a type path generated by subxt from the
+/// metadata, or a user-supplied substitute standing in for it.
+#[derive(Debug)]
+pub enum CompositeDefFieldPath {
+    /// A type path generated by subxt for a metadata type.
+    Generated(TypePath),
+    /// A path to a user-supplied type substituted in place of the metadata type, via a
+    /// [`TypeSubstitutes`] configuration. The substituted composite type is not generated at
+    /// all.
+    Substitute(syn::Path),
+}
+
+impl quote::ToTokens for CompositeDefFieldPath {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let toks = match self {
+            Self::Generated(type_path) => quote!( #type_path ),
+            Self::Substitute(path) => quote!( #path ),
+        };
+        tokens.extend(toks)
+    }
+}
+
 /// Represents a field of a composite type to be generated.
 #[derive(Debug)]
 pub struct CompositeDefFieldType {
     pub type_id: u32,
-    pub type_path: TypePath,
+    pub type_path: CompositeDefFieldPath,
     pub type_name: Option<String>,
+    /// Documentation lifted from the metadata field, emitted as `#[doc = "..."]` attributes.
+    pub docs: Vec<String>,
+    /// Whether this field must be wrapped in `::std::boxed::Box` to give its containing type a
+    /// finite size: [`BoxedFields::should_box`] for the field's edge, but never for a
+    /// [`CompositeDefFieldPath::Substitute`].
+    pub should_box: bool,
 }
 
 impl CompositeDefFieldType {
     /// Construct a new [`CompositeDefFieldType`].
-    pub fn new(type_id: u32, type_path: TypePath, type_name: Option<String>) -> Self {
+    pub fn new(
+        type_id: u32,
+        type_path: CompositeDefFieldPath,
+        type_name: Option<String>,
+        docs: Vec<String>,
+        should_box: bool,
+    ) -> Self {
         CompositeDefFieldType {
             type_id,
             type_path,
             type_name,
+            docs,
+            should_box,
         }
     }
 
-    /// Returns `true` if the field is a [`::std::boxed::Box`].
-    pub fn is_boxed(&self) -> bool {
-        // Use the type name to detect a `Box` field.
-        // Should be updated once `Box` types are no longer erased:
-        // https://github.com/paritytech/scale-info/pull/82
-        matches!(&self.type_name, Some(ty_name) if ty_name.contains("Box<"))
-    }
-
     /// Returns the `#[codec(compact)]` attribute if the type is compact.
     fn compact_attr(&self) -> Option<TokenStream> {
-        self.type_path
-            .is_compact()
-            .then(|| quote!( #[codec(compact)] ))
+        match &self.type_path {
+            CompositeDefFieldPath::Generated(type_path) => {
+                type_path.is_compact().then(|| quote!( #[codec(compact)] ))
+            }
+            // A substituted type is hand-written by the user, so subxt has no business
+            // asserting its compact-ness.
+            CompositeDefFieldPath::Substitute(_) => None,
+        }
     }
 }
 
@@ -337,10 +660,147 @@ impl quote::ToTokens for CompositeDefFieldType {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ty_path = &self.type_path;
 
-        if self.is_boxed() {
+        if self.should_box {
             tokens.extend(quote! { ::std::boxed::Box<#ty_path> })
         } else {
             tokens.extend(quote! { #ty_path })
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enum_variant_def_preserves_out_of_order_metadata_index() {
+        // A variant declared first in the enum, but whose metadata index is 5 (the runtime
+        // declared variants out of order or with gaps) must still carry its metadata index,
+        // not its declaration position.
+        let index: u8 = 5;
+        let def =
+            CompositeDef::enum_variant_def("Foo", index, CompositeDefFields::NoFields, &[], false);
+
+        let expected_attr = quote!(#[codec(index = #index)]).to_string();
+        assert!(quote!(#def).to_string().contains(&expected_attr));
+    }
+
+    #[test]
+    fn doc_attrs_of_no_docs_is_empty() {
+        assert!(doc_attrs(&[]).is_empty());
+    }
+
+    #[test]
+    fn doc_attrs_emits_one_doc_attribute_per_line_in_order() {
+        let docs = vec!["first line".to_string(), "second line".to_string()];
+        let expected = quote! {
+            #[doc = "first line"]
+            #[doc = "second line"]
+        };
+        assert_eq!(doc_attrs(&docs).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn acyclic_graph_boxes_nothing() {
+        let mut graph = TypeSizeGraph::new();
+        // 0 -> 1 -> 2, no cycle.
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 2);
+
+        assert!(!graph.into_boxed_fields().should_box(0, 1));
+    }
+
+    #[test]
+    fn direct_self_cycle_is_boxed() {
+        // A struct with a field of its own type, e.g. `struct List { next: List }`.
+        let mut graph = TypeSizeGraph::new();
+        graph.add_edge(0, 0);
+
+        assert!(graph.into_boxed_fields().should_box(0, 0));
+    }
+
+    #[test]
+    fn mutually_recursive_types_get_exactly_one_boxed_edge() {
+        // A -> B -> A, e.g. `struct A { b: B }` / `struct B { a: A }`.
+        let mut graph = TypeSizeGraph::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+
+        let boxed = graph.into_boxed_fields();
+        // DFS starts at the lowest id (0), discovers 1, then finds the back-edge 1 -> 0.
+        assert!(boxed.should_box(1, 0));
+        assert!(!boxed.should_box(0, 1));
+    }
+
+    #[test]
+    fn edge_already_behind_indirection_is_never_added_so_never_boxed() {
+        // `struct A { b: Vec<B> }` / `struct B { a: A }`: the `Vec` already breaks the size
+        // recursion, so the generator must never record an `A -> B` edge for it, and the only
+        // edge in the graph (`B -> A`) is not itself part of a cycle.
+        let mut graph = TypeSizeGraph::new();
+        graph.add_edge(1, 0);
+
+        assert!(!graph.into_boxed_fields().should_box(1, 0));
+    }
+
+    fn account_id32_path() -> Vec<String> {
+        vec![
+            "sp_core".to_string(),
+            "crypto".to_string(),
+            "AccountId32".to_string(),
+        ]
+    }
+
+    #[test]
+    fn type_substitutes_returns_none_when_no_match() {
+        let substitutes = TypeSubstitutes::new();
+        assert!(substitutes.get(&account_id32_path()).is_none());
+    }
+
+    #[test]
+    fn type_substitutes_returns_configured_path_on_match() {
+        let mut substitutes = TypeSubstitutes::new();
+        let substitute_path: syn::Path = syn::parse_quote!(crate::AccountId32);
+        substitutes.insert(account_id32_path(), substitute_path.clone());
+
+        let found = substitutes.get(&account_id32_path()).unwrap();
+        assert_eq!(quote!(#found).to_string(), quote!(#substitute_path).to_string());
+    }
+
+    #[test]
+    fn type_substitutes_collects_from_a_user_supplied_config_iterator() {
+        let substitute_path: syn::Path = syn::parse_quote!(crate::AccountId32);
+        let substitutes: TypeSubstitutes =
+            std::iter::once((account_id32_path(), substitute_path.clone())).collect();
+
+        let found = substitutes.get(&account_id32_path()).unwrap();
+        assert_eq!(quote!(#found).to_string(), quote!(#substitute_path).to_string());
+    }
+
+    #[test]
+    fn should_generate_composite_is_false_only_for_substituted_paths() {
+        let mut substitutes = TypeSubstitutes::new();
+        substitutes.insert(account_id32_path(), syn::parse_quote!(crate::AccountId32));
+
+        assert!(!should_generate_composite(&substitutes, &account_id32_path()));
+        assert!(should_generate_composite(
+            &substitutes,
+            &["sp_core".to_string(), "crypto".to_string(), "Other".to_string()]
+        ));
+    }
+
+    #[test]
+    fn field_should_box_never_boxes_a_substituted_field() {
+        // Even if the metadata type being substituted happens to sit on a cycle in the type
+        // graph, a substituted field must not be boxed on the generated type's say-so: its
+        // actual layout is opaque to subxt.
+        let mut graph = TypeSizeGraph::new();
+        graph.add_edge(0, 1);
+        graph.add_edge(1, 0);
+        let boxed_fields = graph.into_boxed_fields();
+        assert!(boxed_fields.should_box(1, 0));
+
+        let substitute = CompositeDefFieldPath::Substitute(syn::parse_quote!(crate::Foo));
+        assert!(!field_should_box(&substitute, 1, 0, &boxed_fields));
+    }
+}